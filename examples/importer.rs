@@ -2,7 +2,7 @@ use std::env;
 
 use eyre::Result;
 use flatfs::Flatfs;
-use rocksfs::RocksFs;
+use rocksfs::{RocksFs, RocksFsConfig};
 
 fn main() -> Result<()> {
     let mut args = env::args();
@@ -15,9 +15,7 @@ fn main() -> Result<()> {
         old_path, new_path, limit
     );
 
-    let mut opts = rocksfs::default_options();
-    opts.set_use_direct_io_for_flush_and_compaction(true);
-    opts.set_use_direct_reads(true);
+    let opts = RocksFsConfig::new().use_direct_io(true).build();
 
     let flatfs = Flatfs::new(old_path)?;
     let rocksfs = RocksFs::with_options(&opts, new_path)?;
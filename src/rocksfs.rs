@@ -1,7 +1,190 @@
 use std::path::Path;
 
 use eyre::{eyre, Result};
-use rocksdb::{DBPinnableSlice, Options, WriteBatch, DB};
+use rocksdb::{
+    BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBCompressionType,
+    DBPinnableSlice, Direction, IteratorMode, MergeOperands, Options, ReadOptions, Snapshot,
+    WriteBatch, DB,
+};
+
+/// The options used by [`RocksFs::new`].
+///
+/// Exposed separately so callers that need to tweak the database (e.g.
+/// [`RocksFsConfig::build`], which the importer uses to enable direct IO)
+/// can start from the same defaults.
+pub fn default_options() -> Options {
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_enable_blob_files(true);
+    opts.set_min_blob_size(512 * 1024);
+    opts
+}
+
+/// [`default_options`] with an associative merge operator registered under
+/// `name`, so `merge`/`bulk_merge` calls through the resulting database are
+/// folded by `full_merge_fn` instead of being rejected.
+pub fn default_options_with_merge_operator<F>(name: &str, full_merge_fn: F) -> Options
+where
+    F: Fn(&[u8], Option<&[u8]>, &MergeOperands) -> Option<Vec<u8>> + Clone + Send + Sync + 'static,
+{
+    let mut opts = default_options();
+    opts.set_merge_operator_associative(name, full_merge_fn);
+    opts
+}
+
+/// A ready-to-use merge operator for refcounts: treats stored values as
+/// little-endian `u64` counters and sums the existing value (or zero, if
+/// absent) with all pending merge operands. Missing or short operands are
+/// treated as zero rather than erroring.
+pub fn u64_add_merge_operator(
+    _key: &[u8],
+    existing_val: Option<&[u8]>,
+    operands: &MergeOperands,
+) -> Option<Vec<u8>> {
+    let mut sum = existing_val.map(le_bytes_to_u64).unwrap_or(0);
+    for operand in operands {
+        sum = sum.wrapping_add(le_bytes_to_u64(operand));
+    }
+    Some(sum.to_le_bytes().to_vec())
+}
+
+fn le_bytes_to_u64(bytes: &[u8]) -> u64 {
+    if bytes.len() < 8 {
+        return 0;
+    }
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(buf)
+}
+
+/// The exclusive upper bound of the keyspace starting with `prefix`: `prefix`
+/// with its last non-`0xff` byte incremented and everything after it
+/// dropped. Returns `None` if `prefix` is empty or all `0xff`, in which case
+/// there is no finite upper bound.
+fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut upper = prefix.to_vec();
+    while let Some(&last) = upper.last() {
+        if last == 0xff {
+            upper.pop();
+        } else {
+            *upper.last_mut().unwrap() += 1;
+            return Some(upper);
+        }
+    }
+    None
+}
+
+/// The compression algorithm to use for a column family's SST blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Snappy,
+    Zlib,
+    Lz4,
+    Lz4hc,
+    Zstd,
+}
+
+impl Compression {
+    fn into_db_compression_type(self) -> DBCompressionType {
+        match self {
+            Compression::None => DBCompressionType::None,
+            Compression::Snappy => DBCompressionType::Snappy,
+            Compression::Zlib => DBCompressionType::Zlib,
+            Compression::Lz4 => DBCompressionType::Lz4,
+            Compression::Lz4hc => DBCompressionType::Lz4hc,
+            Compression::Zstd => DBCompressionType::Zstd,
+        }
+    }
+}
+
+/// A builder for the tuning knobs that matter most for a blob filesystem:
+/// compression, blob size, block cache, and direct IO. Build one per column
+/// family to e.g. mix Zstd-compressed cold storage with uncompressed hot
+/// metadata in the same database, via [`RocksFs::with_column_families`].
+#[derive(Debug, Clone)]
+pub struct RocksFsConfig {
+    compression: Compression,
+    bottommost_compression: Option<Compression>,
+    min_blob_size: u64,
+    block_cache_size: Option<usize>,
+    use_direct_io: bool,
+}
+
+impl Default for RocksFsConfig {
+    fn default() -> Self {
+        RocksFsConfig {
+            compression: Compression::None,
+            bottommost_compression: None,
+            min_blob_size: 512 * 1024,
+            block_cache_size: None,
+            use_direct_io: false,
+        }
+    }
+}
+
+impl RocksFsConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compression applied to all but the bottommost level.
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Compression override for the bottommost level, where cold data
+    /// accumulates and a slower, denser algorithm (e.g. Zstd) pays off.
+    pub fn bottommost_compression(mut self, compression: Compression) -> Self {
+        self.bottommost_compression = Some(compression);
+        self
+    }
+
+    /// Values at or above this size are stored in a blob file instead of
+    /// the main SST, keeping compaction cheap for large blobs.
+    pub fn min_blob_size(mut self, min_blob_size: u64) -> Self {
+        self.min_blob_size = min_blob_size;
+        self
+    }
+
+    /// Size, in bytes, of the shared block cache for uncompressed blocks.
+    pub fn block_cache_size(mut self, bytes: usize) -> Self {
+        self.block_cache_size = Some(bytes);
+        self
+    }
+
+    /// Bypass the OS page cache for flushes, compactions and reads.
+    pub fn use_direct_io(mut self, use_direct_io: bool) -> Self {
+        self.use_direct_io = use_direct_io;
+        self
+    }
+
+    /// Translates this configuration into `rocksdb::Options`, ready for
+    /// [`RocksFs::with_options`] or a `ColumnFamilyDescriptor`.
+    pub fn build(&self) -> Options {
+        let mut opts = default_options();
+        opts.set_compression_type(self.compression.into_db_compression_type());
+        if let Some(bottommost) = self.bottommost_compression {
+            opts.set_bottommost_compression_type(bottommost.into_db_compression_type());
+        }
+        opts.set_min_blob_size(self.min_blob_size);
+
+        if let Some(block_cache_size) = self.block_cache_size {
+            let cache = Cache::new_lru_cache(block_cache_size);
+            let mut block_opts = BlockBasedOptions::default();
+            block_opts.set_block_cache(&cache);
+            opts.set_block_based_table_factory(&block_opts);
+        }
+
+        if self.use_direct_io {
+            opts.set_use_direct_io_for_flush_and_compaction(true);
+            opts.set_use_direct_reads(true);
+        }
+
+        opts
+    }
+}
 
 #[derive(Debug)]
 pub struct RocksFs {
@@ -13,16 +196,85 @@ impl RocksFs {
     where
         P: AsRef<Path>,
     {
-        let mut opts = Options::default();
-        opts.create_if_missing(true);
-        opts.set_enable_blob_files(true);
-        opts.set_min_blob_size(512 * 1024);
+        Self::with_options(&default_options(), path)
+    }
+
+    pub fn with_options<P>(opts: &Options, path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let db = DB::open(opts, path)?;
+
+        Ok(RocksFs { db })
+    }
+
+    /// Opens (or creates) a database with the given column families.
+    ///
+    /// Each [`ColumnFamilyDescriptor`] carries its own `Options`, so e.g. a
+    /// small-metadata column family can skip blob files while a large-value
+    /// one enables them. Column families listed here that don't exist yet
+    /// are created automatically.
+    pub fn with_column_families<P>(
+        opts: &Options,
+        path: P,
+        cfs: impl IntoIterator<Item = ColumnFamilyDescriptor>,
+    ) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut opts = opts.clone();
+        opts.create_missing_column_families(true);
+
+        let db = DB::open_cf_descriptors(&opts, path, cfs)?;
+
+        Ok(RocksFs { db })
+    }
+
+    /// Lists the column families of an existing database at `path`, so it
+    /// can be reopened with [`RocksFs::with_column_families`].
+    pub fn list_column_families<P>(opts: &Options, path: P) -> Result<Vec<String>>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(DB::list_cf(opts, path)?)
+    }
 
-        let db = DB::open(&opts, path)?;
+    /// Looks up a previously opened column family by name.
+    pub fn cf_handle(&self, name: &str) -> Result<&ColumnFamily> {
+        self.db
+            .cf_handle(name)
+            .ok_or_else(|| eyre!("column family not found: {name}"))
+    }
+
+    /// Opens `path` read-only, without taking the primary lock, so another
+    /// process can keep writing to it. Writes through the returned handle
+    /// fail with an error rather than silently succeeding.
+    pub fn open_read_only<P>(opts: &Options, path: P, error_if_log_file_exist: bool) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let db = DB::open_for_read_only(opts, path, error_if_log_file_exist)?;
+        Ok(RocksFs { db })
+    }
 
+    /// Opens `primary_path` as a secondary instance, tailing the primary's
+    /// WAL into `secondary_path`. Call [`RocksFs::try_catch_up_with_primary`]
+    /// to refresh the view; like [`RocksFs::open_read_only`], writes through
+    /// the returned handle fail rather than silently succeeding.
+    pub fn open_as_secondary<P>(opts: &Options, primary_path: P, secondary_path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let db = DB::open_as_secondary(opts, primary_path, secondary_path)?;
         Ok(RocksFs { db })
     }
 
+    /// Refreshes a secondary instance opened with
+    /// [`RocksFs::open_as_secondary`] to reflect the primary's latest state.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        Ok(self.db.try_catch_up_with_primary()?)
+    }
+
     pub fn put<K, V>(&self, key: K, value: V) -> Result<()>
     where
         K: AsRef<[u8]>,
@@ -61,6 +313,29 @@ impl RocksFs {
         Ok(self.db.write(batch)?)
     }
 
+    /// Applies a merge operand for `key`, to be folded with the existing
+    /// value (and any other pending operands) by the merge operator
+    /// registered on this database's `Options`.
+    pub fn merge<K, V>(&self, key: K, value: V) -> Result<()>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        Ok(self.db.merge(key, value)?)
+    }
+
+    pub fn bulk_merge<'b, K, V>(&self, values: impl Iterator<Item = (&'b K, &'b V)>) -> Result<()>
+    where
+        K: AsRef<[u8]> + 'b,
+        V: AsRef<[u8]> + 'b,
+    {
+        let mut batch = WriteBatch::default();
+        for (k, v) in values {
+            batch.merge(k, v);
+        }
+        Ok(self.db.write(batch)?)
+    }
+
     pub fn get<K>(&self, key: K) -> Result<DBPinnableSlice<'_>>
     where
         K: AsRef<[u8]>,
@@ -93,11 +368,127 @@ impl RocksFs {
             .map_err(Into::into)
     }
 
+    pub fn put_cf<K, V>(&self, cf: &ColumnFamily, key: K, value: V) -> Result<()>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        Ok(self.db.put_cf(cf, key, value)?)
+    }
+
+    pub fn get_cf<K>(&self, cf: &ColumnFamily, key: K) -> Result<DBPinnableSlice<'_>>
+    where
+        K: AsRef<[u8]>,
+    {
+        let res = self
+            .db
+            .get_pinned_cf(cf, key)?
+            .ok_or_else(|| eyre!("key not found"))?;
+        Ok(res)
+    }
+
+    pub fn del_cf<K>(&self, cf: &ColumnFamily, key: K) -> Result<()>
+    where
+        K: AsRef<[u8]>,
+    {
+        Ok(self.db.delete_cf(cf, key)?)
+    }
+
+    pub fn bulk_put_cf<'b, K, V>(
+        &self,
+        cf: &ColumnFamily,
+        values: impl Iterator<Item = (&'b K, &'b V)>,
+    ) -> Result<()>
+    where
+        K: AsRef<[u8]> + 'b,
+        V: AsRef<[u8]> + 'b,
+    {
+        let mut batch = WriteBatch::default();
+        for (k, v) in values {
+            batch.put_cf(cf, k, v);
+        }
+        Ok(self.db.write(batch)?)
+    }
+
+    /// Iterates over all key/value pairs in `cf`, in key order.
+    pub fn iter_cf<'a>(
+        &'a self,
+        cf: &ColumnFamily,
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + 'a {
+        self.db
+            .iterator_cf(cf, IteratorMode::Start)
+            .map(|r| r.map_err(Into::into))
+    }
+
+    /// Iterates over all key/value pairs in the database, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + '_ {
+        self.iter_opt(IteratorMode::Start, ReadOptions::default())
+    }
+
+    /// Iterates over all keys in the database, in key order.
+    pub fn keys(&self) -> impl Iterator<Item = Result<Box<[u8]>>> + '_ {
+        self.iter().map(|r| r.map(|(key, _)| key))
+    }
+
+    /// Iterates over all values in the database, in key order.
+    pub fn values(&self) -> impl Iterator<Item = Result<Box<[u8]>>> + '_ {
+        self.iter().map(|r| r.map(|(_, value)| value))
+    }
+
+    /// Iterates over all key/value pairs whose key starts with `prefix`.
+    ///
+    /// The iterator stops as soon as it walks past the prefix, rather than
+    /// continuing on to the rest of the keyspace.
+    pub fn prefix_iter<'a>(
+        &'a self,
+        prefix: &[u8],
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + 'a {
+        let mut read_opts = ReadOptions::default();
+        if let Some(upper_bound) = prefix_upper_bound(prefix) {
+            read_opts.set_iterate_upper_bound(upper_bound);
+        }
+        self.iter_opt(IteratorMode::From(prefix, Direction::Forward), read_opts)
+    }
+
+    /// Iterates over all key/value pairs in `[start, end)`.
+    pub fn range_iter<'a>(
+        &'a self,
+        start: &[u8],
+        end: &[u8],
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + 'a {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_iterate_upper_bound(end.to_vec());
+        self.iter_opt(IteratorMode::From(start, Direction::Forward), read_opts)
+    }
+
+    fn iter_opt(
+        &self,
+        mode: IteratorMode<'_>,
+        read_opts: ReadOptions,
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + '_ {
+        self.db
+            .iterator_opt(mode, read_opts)
+            .map(|r| r.map_err(Into::into))
+    }
+
     /// Deletes all elements in the database.
     pub fn clear(&self) -> Result<()> {
-        for (key, _) in self.db.full_iterator(rocksdb::IteratorMode::Start) {
-            self.db.delete(key)?;
-        }
+        let first = match self.iter().next() {
+            Some(r) => r?.0,
+            None => return Ok(()),
+        };
+        let (last, _) = self
+            .db
+            .full_iterator(rocksdb::IteratorMode::End)
+            .next()
+            .expect("store is non-empty")?;
+
+        let default_cf = self
+            .db
+            .cf_handle(rocksdb::DEFAULT_COLUMN_FAMILY_NAME)
+            .expect("default column family always exists");
+        self.db.delete_range_cf(default_cf, &first, &last)?;
+        self.db.delete(&last)?;
 
         Ok(())
     }
@@ -109,6 +500,96 @@ impl RocksFs {
             .unwrap_or_default();
         Ok(keys)
     }
+
+    /// Captures a consistent point-in-time view of the store.
+    ///
+    /// The returned handle borrows the database, so it cannot outlive it,
+    /// and releases the underlying RocksDB snapshot on drop.
+    pub fn snapshot(&self) -> RocksFsSnapshot<'_> {
+        RocksFsSnapshot {
+            db: &self.db,
+            snapshot: self.db.snapshot(),
+        }
+    }
+}
+
+/// A consistent, point-in-time view of a [`RocksFs`], created via
+/// [`RocksFs::snapshot`].
+///
+/// All reads through this handle are pinned to the sequence number captured
+/// at creation time, so concurrent writes to the store are not observed.
+#[derive(Debug)]
+pub struct RocksFsSnapshot<'a> {
+    db: &'a DB,
+    snapshot: Snapshot<'a>,
+}
+
+impl<'a> RocksFsSnapshot<'a> {
+    fn read_opts(&self) -> ReadOptions {
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_snapshot(&self.snapshot);
+        read_opts
+    }
+
+    pub fn get<K>(&self, key: K) -> Result<DBPinnableSlice<'_>>
+    where
+        K: AsRef<[u8]>,
+    {
+        let res = self
+            .db
+            .get_pinned_opt(key, &self.read_opts())?
+            .ok_or_else(|| eyre!("key not found"))?;
+        Ok(res)
+    }
+
+    pub fn get_size<K>(&self, key: K) -> Result<usize>
+    where
+        K: AsRef<[u8]>,
+    {
+        let res = self
+            .db
+            .get_pinned_opt(key, &self.read_opts())?
+            .ok_or_else(|| eyre!("key not found"))?;
+        Ok(res.len())
+    }
+
+    pub fn has<K>(&self, key: K) -> Result<bool>
+    where
+        K: AsRef<[u8]>,
+    {
+        self.db
+            .get_pinned_opt(key, &self.read_opts())
+            .map(|v| v.is_some())
+            .map_err(Into::into)
+    }
+
+    /// Iterates over all key/value pairs in the snapshot, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + '_ {
+        self.iter_opt(IteratorMode::Start, self.read_opts())
+    }
+
+    /// Iterates over all key/value pairs in the snapshot whose key starts
+    /// with `prefix`.
+    pub fn prefix_iter<'b>(
+        &'b self,
+        prefix: &[u8],
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + 'b {
+        let mut read_opts = self.read_opts();
+        if let Some(upper_bound) = prefix_upper_bound(prefix) {
+            read_opts.set_iterate_upper_bound(upper_bound);
+        }
+        self.iter_opt(IteratorMode::From(prefix, Direction::Forward), read_opts)
+    }
+
+    fn iter_opt(
+        &self,
+        mode: IteratorMode<'_>,
+        read_opts: ReadOptions,
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + '_ {
+        self.db
+            .iterator_opt(mode, read_opts)
+            .map(|r| r.map_err(Into::into))
+    }
 }
 
 #[cfg(test)]
@@ -200,21 +681,250 @@ mod tests {
 
         assert_eq!(rocksfs.number_of_keys().unwrap(), 10);
 
-        // for r in rocksfs.iter() {
-        //     let (key, value) = r.unwrap();
-        //     let i: u8 = key.strip_prefix("foo").unwrap().parse().unwrap();
-        //     assert_eq!(value, [i; 128]);
-        // }
-
-        // for r in rocksfs.keys() {
-        //     let key = r.unwrap();
-        //     let i: u8 = key.strip_prefix("foo").unwrap().parse().unwrap();
-        //     assert!(i < 10);
-        // }
-
-        // for r in rocksfs.values() {
-        //     let value = r.unwrap();
-        //     assert_eq!(value.len(), 128);
-        // }
+        let mut count = 0;
+        for r in rocksfs.iter() {
+            let (key, value) = r.unwrap();
+            let key = std::str::from_utf8(&key).unwrap();
+            let i: u8 = key.strip_prefix("foo").unwrap().parse().unwrap();
+            assert_eq!(&value[..], [i; 128]);
+            count += 1;
+        }
+        assert_eq!(count, 10);
+
+        for r in rocksfs.keys() {
+            let key = r.unwrap();
+            let key = std::str::from_utf8(&key).unwrap();
+            let i: u8 = key.strip_prefix("foo").unwrap().parse().unwrap();
+            assert!(i < 10);
+        }
+
+        for r in rocksfs.values() {
+            let value = r.unwrap();
+            assert_eq!(value.len(), 128);
+        }
+    }
+
+    #[test]
+    fn test_prefix_iter() {
+        let dir = tempfile::tempdir().unwrap();
+        let rocksfs = RocksFs::new(dir.path()).unwrap();
+
+        for i in 0..10 {
+            rocksfs.put(&format!("foo{i}"), [i; 128]).unwrap();
+        }
+        for i in 0..3 {
+            rocksfs.put(&format!("bar{i}"), [i; 64]).unwrap();
+        }
+        for i in 0..3 {
+            rocksfs.put(&format!("zoo{i}"), [i; 64]).unwrap();
+        }
+
+        let mut count = 0;
+        for r in rocksfs.prefix_iter(b"foo") {
+            let (key, _) = r.unwrap();
+            assert!(key.starts_with(b"foo"));
+            count += 1;
+        }
+        assert_eq!(count, 10);
+    }
+
+    #[test]
+    fn test_range_iter() {
+        let dir = tempfile::tempdir().unwrap();
+        let rocksfs = RocksFs::new(dir.path()).unwrap();
+
+        for i in 0..10 {
+            rocksfs.put(&format!("foo{i}"), [i; 128]).unwrap();
+        }
+
+        let keys: Vec<_> = rocksfs
+            .range_iter(b"foo2", b"foo5")
+            .map(|r| r.unwrap().0)
+            .collect();
+
+        assert_eq!(
+            keys,
+            vec![
+                b"foo2".to_vec().into_boxed_slice(),
+                b"foo3".to_vec().into_boxed_slice(),
+                b"foo4".to_vec().into_boxed_slice(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let rocksfs = RocksFs::new(dir.path()).unwrap();
+
+        for i in 0..10 {
+            rocksfs.put(&format!("foo{i}"), [i; 128]).unwrap();
+        }
+        assert_eq!(rocksfs.number_of_keys().unwrap(), 10);
+
+        rocksfs.clear().unwrap();
+        assert_eq!(rocksfs.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_snapshot_isolated_from_later_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let rocksfs = RocksFs::new(dir.path()).unwrap();
+
+        for i in 0..10 {
+            rocksfs.put(&format!("foo{i}"), [i; 128]).unwrap();
+        }
+
+        let snapshot = rocksfs.snapshot();
+        assert_eq!(snapshot.iter().count(), 10);
+        assert_eq!(&snapshot.get("foo0").unwrap()[..], [0; 128]);
+
+        rocksfs.put("foo10", [10; 128]).unwrap();
+        rocksfs.del("foo0").unwrap();
+
+        // The snapshot still sees the state as of its creation.
+        assert_eq!(snapshot.iter().count(), 10);
+        assert!(snapshot.has("foo0").unwrap());
+        assert!(!snapshot.has("foo10").unwrap());
+
+        // While the live store reflects the new writes.
+        assert!(!rocksfs.has("foo0").unwrap());
+        assert!(rocksfs.has("foo10").unwrap());
+    }
+
+    #[test]
+    fn test_snapshot_prefix_iter() {
+        let dir = tempfile::tempdir().unwrap();
+        let rocksfs = RocksFs::new(dir.path()).unwrap();
+
+        for i in 0..10 {
+            rocksfs.put(&format!("foo{i}"), [i; 128]).unwrap();
+        }
+        for i in 0..3 {
+            rocksfs.put(&format!("zoo{i}"), [i; 64]).unwrap();
+        }
+
+        let snapshot = rocksfs.snapshot();
+
+        let mut count = 0;
+        for r in snapshot.prefix_iter(b"foo") {
+            let (key, _) = r.unwrap();
+            assert!(key.starts_with(b"foo"));
+            count += 1;
+        }
+        assert_eq!(count, 10);
+    }
+
+    #[test]
+    fn test_column_families() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let opts = default_options();
+        let rocksfs = RocksFs::with_column_families(
+            &opts,
+            dir.path(),
+            [
+                ColumnFamilyDescriptor::new("blocks", default_options()),
+                ColumnFamilyDescriptor::new("pins", default_options()),
+            ],
+        )
+        .unwrap();
+
+        let blocks = rocksfs.cf_handle("blocks").unwrap();
+        let pins = rocksfs.cf_handle("pins").unwrap();
+
+        rocksfs.put_cf(blocks, "foo", [1u8; 128]).unwrap();
+        rocksfs.put_cf(pins, "foo", [2u8; 64]).unwrap();
+
+        assert_eq!(&rocksfs.get_cf(blocks, "foo").unwrap()[..], [1u8; 128]);
+        assert_eq!(&rocksfs.get_cf(pins, "foo").unwrap()[..], [2u8; 64]);
+
+        rocksfs.del_cf(blocks, "foo").unwrap();
+        assert!(rocksfs.get_cf(blocks, "foo").is_err());
+        assert_eq!(&rocksfs.get_cf(pins, "foo").unwrap()[..], [2u8; 64]);
+
+        assert!(rocksfs.cf_handle("missing").is_err());
+
+        drop(rocksfs);
+
+        let mut names = RocksFs::list_column_families(&opts, dir.path()).unwrap();
+        names.sort();
+        assert_eq!(names, vec!["blocks", "default", "pins"]);
+    }
+
+    #[test]
+    fn test_merge_u64_add() {
+        let dir = tempfile::tempdir().unwrap();
+        let opts = default_options_with_merge_operator("u64_add", u64_add_merge_operator);
+        let rocksfs = RocksFs::with_options(&opts, dir.path()).unwrap();
+
+        // Merging against a missing key starts from zero.
+        rocksfs.merge("refcount", 1u64.to_le_bytes()).unwrap();
+        rocksfs.merge("refcount", 2u64.to_le_bytes()).unwrap();
+        assert_eq!(
+            u64::from_le_bytes(rocksfs.get("refcount").unwrap()[..].try_into().unwrap()),
+            3
+        );
+
+        let deltas: Vec<_> = [4u64, 5u64].iter().map(|d| d.to_le_bytes()).collect();
+        let key = b"refcount".to_vec();
+        rocksfs
+            .bulk_merge(deltas.iter().map(|d| (&key, d)))
+            .unwrap();
+        assert_eq!(
+            u64::from_le_bytes(rocksfs.get("refcount").unwrap()[..].try_into().unwrap()),
+            3 + 4 + 5
+        );
+    }
+
+    #[test]
+    fn test_open_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let rocksfs = RocksFs::new(dir.path()).unwrap();
+        rocksfs.put("foo", [1u8; 128]).unwrap();
+        drop(rocksfs);
+
+        let reader = RocksFs::open_read_only(&default_options(), dir.path(), false).unwrap();
+        assert_eq!(&reader.get("foo").unwrap()[..], [1u8; 128]);
+        assert!(reader.put("bar", [2u8; 128]).is_err());
+    }
+
+    #[test]
+    fn test_open_as_secondary() {
+        let primary_dir = tempfile::tempdir().unwrap();
+        let secondary_dir = tempfile::tempdir().unwrap();
+
+        let primary = RocksFs::new(primary_dir.path()).unwrap();
+        primary.put("foo", [1u8; 128]).unwrap();
+
+        let secondary = RocksFs::open_as_secondary(
+            &default_options(),
+            primary_dir.path(),
+            secondary_dir.path(),
+        )
+        .unwrap();
+        assert_eq!(&secondary.get("foo").unwrap()[..], [1u8; 128]);
+        assert!(secondary.put("bar", [2u8; 128]).is_err());
+
+        primary.put("bar", [2u8; 128]).unwrap();
+        secondary.try_catch_up_with_primary().unwrap();
+        assert_eq!(&secondary.get("bar").unwrap()[..], [2u8; 128]);
+    }
+
+    #[test]
+    fn test_config_builder() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let opts = RocksFsConfig::new()
+            .compression(Compression::Zstd)
+            .bottommost_compression(Compression::Zstd)
+            .min_blob_size(16)
+            .block_cache_size(8 * 1024 * 1024)
+            .build();
+
+        let rocksfs = RocksFs::with_options(&opts, dir.path()).unwrap();
+        rocksfs.put("foo", [1u8; 128]).unwrap();
+        assert_eq!(&rocksfs.get("foo").unwrap()[..], [1u8; 128]);
     }
 }